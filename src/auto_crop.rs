@@ -0,0 +1,405 @@
+//! Automatic white-margin detection, mirroring the original TeX `pdfcrop`
+//! heuristic: rasterize each page, scan inward from every edge until a
+//! non-white pixel is found, then convert that pixel box back to PDF points.
+
+use js_sys::Function;
+use pdfcrop::wasm::{page_media_box, page_count, render_page_rgba, set_crop_boxes, CropBox};
+use wasm_bindgen::prelude::*;
+
+use crate::progress::{CancelToken, PartialResult, ProgressReporter};
+
+/// How the per-page boxes detected by [`auto_crop`] are combined before
+/// being applied back to the document.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutoCropMode {
+    /// Every page gets the union of all detected content boxes.
+    Uniform,
+    /// Odd and even pages are unioned separately, so book scans with an
+    /// asymmetric gutter still crop symmetrically within each side.
+    OddEven,
+}
+
+/// Margin added around the detected content box, in PDF points.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Margin {
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+}
+
+#[wasm_bindgen]
+impl Margin {
+    #[wasm_bindgen(constructor)]
+    pub fn new(top: f32, bottom: f32, left: f32, right: f32) -> Margin {
+        Margin { top, bottom, left, right }
+    }
+
+    /// Convenience constructor for the common case of equal margins on all sides.
+    pub fn uniform(amount: f32) -> Margin {
+        Margin { top: amount, bottom: amount, left: amount, right: amount }
+    }
+}
+
+/// Options controlling [`auto_crop`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct AutoCropOptions {
+    /// DPI used to rasterize each page before scanning for content. Higher
+    /// values find tighter boxes at the cost of more work; 72-150 covers
+    /// the practical range.
+    pub dpi: f32,
+    /// Luminance (0.0-1.0) at or above which a pixel is considered
+    /// "white" and skipped. Values slightly below 1.0 (default ~0.99)
+    /// tolerate anti-aliasing and scanner speckle.
+    pub whiteness_threshold: f32,
+    pub margin: Margin,
+    pub mode: AutoCropMode,
+}
+
+#[wasm_bindgen]
+impl AutoCropOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new(dpi: f32, whiteness_threshold: f32, margin: Margin, mode: AutoCropMode) -> AutoCropOptions {
+        AutoCropOptions { dpi, whiteness_threshold, margin, mode }
+    }
+}
+
+impl Default for AutoCropOptions {
+    fn default() -> Self {
+        AutoCropOptions {
+            dpi: 150.0,
+            whiteness_threshold: 0.99,
+            margin: Margin::default(),
+            mode: AutoCropMode::Uniform,
+        }
+    }
+}
+
+/// A detected content box in PDF points (bottom-left origin), or `None`
+/// when a page was blank and contributed nothing to the union.
+#[derive(Clone, Copy, Debug)]
+struct DetectedBox {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+}
+
+fn luminance(r: u8, g: u8, b: u8) -> f32 {
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0
+}
+
+/// Scans a rasterized page for the tightest pixel box containing any
+/// non-white pixel, returning `None` if the whole page is blank.
+fn detect_content_pixels(width: u32, height: u32, pixels: &[u8], threshold: f32) -> Option<(u32, u32, u32, u32)> {
+    let is_content = |x: u32, y: u32| -> bool {
+        let idx = ((y * width + x) * 4) as usize;
+        let (r, g, b) = (pixels[idx], pixels[idx + 1], pixels[idx + 2]);
+        luminance(r, g, b) < threshold
+    };
+
+    let mut top = None;
+    for y in 0..height {
+        if (0..width).any(|x| is_content(x, y)) {
+            top = Some(y);
+            break;
+        }
+    }
+    let top = top?;
+
+    let mut bottom = height - 1;
+    for y in (0..height).rev() {
+        if (0..width).any(|x| is_content(x, y)) {
+            bottom = y;
+            break;
+        }
+    }
+
+    let mut left = 0;
+    for x in 0..width {
+        if (top..=bottom).any(|y| is_content(x, y)) {
+            left = x;
+            break;
+        }
+    }
+
+    let mut right = width - 1;
+    for x in (0..width).rev() {
+        if (top..=bottom).any(|y| is_content(x, y)) {
+            right = x;
+            break;
+        }
+    }
+
+    Some((left, top, right, bottom))
+}
+
+/// Converts a pixel-space box (top-left origin) detected at `dpi` into a
+/// PDF-points box (bottom-left origin), anchored at the page's MediaBox.
+fn pixels_to_points(
+    px: (u32, u32, u32, u32),
+    raster_height: u32,
+    media_box: [f32; 4],
+    dpi: f32,
+) -> DetectedBox {
+    let scale = 72.0 / dpi;
+    let (left, top, right, bottom) = px;
+
+    DetectedBox {
+        x0: media_box[0] + left as f32 * scale,
+        y0: media_box[1] + (raster_height as f32 - 1.0 - bottom as f32) * scale,
+        x1: media_box[0] + (right as f32 + 1.0) * scale,
+        y1: media_box[1] + (raster_height as f32 - top as f32) * scale,
+    }
+}
+
+fn expand_and_clamp(b: DetectedBox, margin: Margin, media_box: [f32; 4]) -> DetectedBox {
+    DetectedBox {
+        x0: (b.x0 - margin.left).max(media_box[0]),
+        y0: (b.y0 - margin.bottom).max(media_box[1]),
+        x1: (b.x1 + margin.right).min(media_box[2]),
+        y1: (b.y1 + margin.top).min(media_box[3]),
+    }
+}
+
+fn union(a: DetectedBox, b: DetectedBox) -> DetectedBox {
+    DetectedBox {
+        x0: a.x0.min(b.x0),
+        y0: a.y0.min(b.y0),
+        x1: a.x1.max(b.x1),
+        y1: a.y1.max(b.y1),
+    }
+}
+
+/// Detects the content bounding box of every page in `pdf_bytes` and
+/// returns the union over all pages as `[x0, y0, x1, y1]` points;
+/// `options.mode` is not consulted. Used by callers (such as batch cropping) that need the box
+/// before any page is actually cropped. Returns an error if no page has
+/// any detectable content.
+pub(crate) fn detect_union_box(pdf_bytes: &[u8], options: AutoCropOptions) -> Result<[f32; 4], JsValue> {
+    let pages = page_count(pdf_bytes)?;
+    let mut merged: Option<DetectedBox> = None;
+
+    for page_index in 0..pages {
+        let media_box = page_media_box(pdf_bytes, page_index)?;
+        let rendered = render_page_rgba(pdf_bytes, page_index, options.dpi)?;
+        if let Some(px) = detect_content_pixels(rendered.width, rendered.height, &rendered.pixels, options.whiteness_threshold) {
+            let raw = pixels_to_points(px, rendered.height, media_box, options.dpi);
+            let b = expand_and_clamp(raw, options.margin, media_box);
+            merged = Some(match merged {
+                Some(m) => union(m, b),
+                None => b,
+            });
+        }
+    }
+
+    merged
+        .map(|b| [b.x0, b.y0, b.x1, b.y1])
+        .ok_or_else(|| JsValue::from_str("no page had any detectable content"))
+}
+
+/// Like [`auto_crop_cancellable`], but without progress reporting or
+/// cancellation, for callers (such as batch cropping) that drive their own
+/// coarser-grained progress instead.
+pub(crate) fn auto_crop_with_boxes(
+    pdf_bytes: &[u8],
+    options: AutoCropOptions,
+    override_box: Option<[f32; 4]>,
+) -> Result<Vec<u8>, JsValue> {
+    Ok(auto_crop_cancellable(pdf_bytes, options, override_box, None, None)?.bytes)
+}
+
+/// Detects the content bounding box of every page in `pdf_bytes` and crops
+/// each page to it, expanded by `options.margin`. Pages that are entirely
+/// blank are skipped when computing the union and are then cropped to that
+/// union like the other pages; pages keep their original MediaBox only
+/// when no page of their group (all pages in uniform mode, pages of the
+/// same parity in odd/even mode) has any content.
+///
+/// When `override_box` is `Some`, it is used as the uniform crop box
+/// instead of a freshly detected one, clamped to each page's MediaBox
+/// (`options.margin` is not applied to it again), so a box detected across an entire batch of files can be applied to
+/// each file individually.
+///
+/// `on_progress`, if given, is invoked as `(pages_done, pages_total)`
+/// after each page's rasterization pass; `cancel`, if given, is checked
+/// at the same point, and a cancelled job stops rasterizing further pages
+/// and returns whatever crop boxes were detected so far (applied only to
+/// the pages already scanned; later pages keep their original MediaBox).
+pub(crate) fn auto_crop_cancellable(
+    pdf_bytes: &[u8],
+    options: AutoCropOptions,
+    override_box: Option<[f32; 4]>,
+    on_progress: Option<&Function>,
+    cancel: Option<&CancelToken>,
+) -> Result<PartialResult, JsValue> {
+    let pages = page_count(pdf_bytes)?;
+    let reporter = ProgressReporter::new(on_progress, cancel, pages);
+
+    let mut media_boxes = Vec::with_capacity(pages as usize);
+    let mut detected: Vec<Option<DetectedBox>> = Vec::with_capacity(pages as usize);
+    let mut was_cancelled = false;
+
+    for page_index in 0..pages {
+        let media_box = page_media_box(pdf_bytes, page_index)?;
+        media_boxes.push(media_box);
+
+        if override_box.is_none() {
+            let rendered = render_page_rgba(pdf_bytes, page_index, options.dpi)?;
+            let content_px = detect_content_pixels(
+                rendered.width,
+                rendered.height,
+                &rendered.pixels,
+                options.whiteness_threshold,
+            );
+
+            detected.push(content_px.map(|px| {
+                let raw = pixels_to_points(px, rendered.height, media_box, options.dpi);
+                expand_and_clamp(raw, options.margin, media_box)
+            }));
+        }
+
+        if reporter.report(page_index + 1) {
+            was_cancelled = true;
+            break;
+        }
+    }
+
+    // `media_boxes` has one entry per page actually iterated, which is all
+    // of `pages` unless cancellation cut the loop short; use its length
+    // rather than `pages` from here on so a cancelled job never indexes
+    // past the pages it scanned.
+    let pages_scanned = media_boxes.len() as u32;
+
+    let unioned: Vec<Option<DetectedBox>> = if let Some([x0, y0, x1, y1]) = override_box {
+        media_boxes
+            .iter()
+            .map(|&mb| Some(expand_and_clamp(DetectedBox { x0, y0, x1, y1 }, Margin::default(), mb)))
+            .collect()
+    } else {
+        match options.mode {
+            AutoCropMode::Uniform => {
+                let merged = detected.iter().flatten().copied().reduce(union);
+                vec![merged; pages_scanned as usize]
+            }
+            AutoCropMode::OddEven => {
+                let odd = detected.iter().step_by(2).flatten().copied().reduce(union);
+                let even = detected.iter().skip(1).step_by(2).flatten().copied().reduce(union);
+                (0..pages_scanned as usize)
+                    .map(|i| if i % 2 == 0 { odd } else { even })
+                    .collect()
+            }
+        }
+    };
+
+    // Pages beyond `pages_scanned` were never rasterized because
+    // cancellation cut the loop short; leave them out of the crop-box list
+    // entirely so `set_crop_boxes` leaves their original MediaBox alone,
+    // matching the "later pages keep their original MediaBox" contract.
+    let boxes: Vec<CropBox> = (0..pages_scanned as usize)
+        .map(|i| {
+            let fallback = media_boxes[i];
+            let b = unioned[i].unwrap_or(DetectedBox {
+                x0: fallback[0],
+                y0: fallback[1],
+                x1: fallback[2],
+                y1: fallback[3],
+            });
+            CropBox {
+                page_index: i as u32,
+                x0: b.x0,
+                y0: b.y0,
+                x1: b.x1,
+                y1: b.y1,
+            }
+        })
+        .collect();
+
+    let bytes = set_crop_boxes(pdf_bytes, boxes)?;
+    Ok(PartialResult { bytes, cancelled: was_cancelled, pages_completed: pages_scanned })
+}
+
+/// Detects the content bounding box of every page in `pdf_bytes` and crops
+/// each page to it, expanded by `options.margin`. Pages that are entirely
+/// blank are skipped when computing the union and are then cropped to that
+/// union like the other pages; pages keep their original MediaBox only
+/// when no page of their group (all pages in uniform mode, pages of the
+/// same parity in odd/even mode) has any content.
+///
+/// `on_progress`, if given, is called as `(pages_done, pages_total)` after
+/// each page is scanned, so a Web Worker can drive a progress bar.
+/// `cancel`, if given, lets the caller abort between pages; the result's
+/// `cancelled` flag and `pages_completed` count tell the caller whether it
+/// got the full crop or a partial one.
+#[wasm_bindgen]
+pub fn auto_crop(
+    pdf_bytes: &[u8],
+    options: AutoCropOptions,
+    on_progress: Option<Function>,
+    cancel: Option<CancelToken>,
+) -> Result<PartialResult, JsValue> {
+    auto_crop_cancellable(pdf_bytes, options, None, on_progress.as_ref(), cancel.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn white_page(width: u32, height: u32) -> Vec<u8> {
+        vec![255u8; (width * height * 4) as usize]
+    }
+
+    fn set_pixel(pixels: &mut [u8], width: u32, x: u32, y: u32, rgb: (u8, u8, u8)) {
+        let idx = ((y * width + x) * 4) as usize;
+        pixels[idx] = rgb.0;
+        pixels[idx + 1] = rgb.1;
+        pixels[idx + 2] = rgb.2;
+        pixels[idx + 3] = 255;
+    }
+
+    #[test]
+    fn detect_content_pixels_skips_blank_page() {
+        let pixels = white_page(10, 10);
+        assert_eq!(detect_content_pixels(10, 10, &pixels, 0.99), None);
+    }
+
+    #[test]
+    fn detect_content_pixels_finds_tightest_box() {
+        let mut pixels = white_page(10, 10);
+        set_pixel(&mut pixels, 10, 3, 2, (0, 0, 0));
+        set_pixel(&mut pixels, 10, 6, 5, (0, 0, 0));
+
+        assert_eq!(detect_content_pixels(10, 10, &pixels, 0.99), Some((3, 2, 6, 5)));
+    }
+
+    #[test]
+    fn pixels_to_points_converts_and_flips_y_axis() {
+        // A 100x100 raster at 72 dpi is 1:1 with points; page origin at (0, 0).
+        let media_box = [0.0, 0.0, 100.0, 100.0];
+        let b = pixels_to_points((10, 20, 30, 40), 100, media_box, 72.0);
+
+        assert_eq!(b.x0, 10.0);
+        assert_eq!(b.x1, 31.0);
+        // Pixel rows are top-left origin, points are bottom-left origin.
+        assert_eq!(b.y0, 59.0);
+        assert_eq!(b.y1, 80.0);
+    }
+
+    #[test]
+    fn expand_and_clamp_stays_within_media_box() {
+        let media_box = [0.0, 0.0, 100.0, 100.0];
+        let b = DetectedBox { x0: 10.0, y0: 10.0, x1: 90.0, y1: 90.0 };
+
+        let expanded = expand_and_clamp(b, Margin::uniform(5.0), media_box);
+        assert_eq!((expanded.x0, expanded.y0, expanded.x1, expanded.y1), (5.0, 5.0, 95.0, 95.0));
+
+        let over_expanded = expand_and_clamp(b, Margin::uniform(50.0), media_box);
+        assert_eq!(
+            (over_expanded.x0, over_expanded.y0, over_expanded.x1, over_expanded.y1),
+            (0.0, 0.0, 100.0, 100.0)
+        );
+    }
+}
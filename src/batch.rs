@@ -0,0 +1,235 @@
+//! Bulk-cropping a set of PDFs (or a ZIP archive of them) with a single
+//! shared crop specification, for processing scanned document sets.
+
+use std::io::{Cursor, Read, Write};
+
+use js_sys::Function;
+use pdfcrop::wasm::{page_count, set_crop_boxes, CropBox};
+use wasm_bindgen::prelude::*;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+use crate::auto_crop::{auto_crop_with_boxes, AutoCropOptions};
+use crate::progress::{CancelToken, ProgressReporter};
+
+/// How the crop box is determined for a [`crop_batch`] call.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BatchCropSpec {
+    /// Apply the same explicit box to every page of every file.
+    Explicit,
+    /// Auto-detect content per file, unioning only within each file.
+    AutoCropPerFile,
+    /// Auto-detect content across the whole batch, so every file ends up
+    /// cropped to the same box.
+    AutoCropAcrossBatch,
+}
+
+/// Result of cropping a single file within a batch.
+#[wasm_bindgen(getter_with_clone)]
+pub struct BatchFileResult {
+    pub name: String,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// For [`BatchCropSpec::AutoCropAcrossBatch`], every file must be cropped
+/// to the same box, so that box has to be known before any file is
+/// actually cropped. This does a first pass over the archive purely to
+/// detect and union each file's content box; a corrupt or unparsable file
+/// simply doesn't contribute to the union here (when it's actually
+/// cropped, `crop_batch` leaves it out and `crop_batch_report` reports
+/// its error).
+fn compute_shared_box(zip_bytes: &[u8], auto_options: AutoCropOptions) -> Option<[f32; 4]> {
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes)).ok()?;
+    let mut merged: Option<[f32; 4]> = None;
+
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else { continue };
+        if entry.is_dir() {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        if entry.read_to_end(&mut bytes).is_err() {
+            continue;
+        }
+        if let Ok(detected) = crate::auto_crop::detect_union_box(&bytes, auto_options) {
+            merged = Some(match merged {
+                Some(m) => [
+                    m[0].min(detected[0]),
+                    m[1].min(detected[1]),
+                    m[2].max(detected[2]),
+                    m[3].max(detected[3]),
+                ],
+                None => detected,
+            });
+        }
+    }
+
+    merged
+}
+
+fn crop_one(pdf_bytes: &[u8], spec: BatchCropSpec, explicit_box: [f32; 4], auto_options: AutoCropOptions, shared_box: Option<[f32; 4]>) -> Result<Vec<u8>, JsValue> {
+    match spec {
+        BatchCropSpec::Explicit => {
+            let boxes: Vec<CropBox> = (0..page_count(pdf_bytes)?)
+                .map(|page_index| CropBox {
+                    page_index,
+                    x0: explicit_box[0],
+                    y0: explicit_box[1],
+                    x1: explicit_box[2],
+                    y1: explicit_box[3],
+                })
+                .collect();
+            set_crop_boxes(pdf_bytes, boxes)
+        }
+        BatchCropSpec::AutoCropPerFile => auto_crop_with_boxes(pdf_bytes, auto_options, None),
+        BatchCropSpec::AutoCropAcrossBatch => auto_crop_with_boxes(pdf_bytes, auto_options, shared_box),
+    }
+}
+
+/// Crops every PDF in `zip_bytes` (a ZIP archive of `.pdf` files) using the
+/// same crop specification, and returns a ZIP of the cropped outputs.
+///
+/// Files are processed sequentially, in archive order, to bound memory
+/// usage for large batches. A file that fails to parse or crop is left out
+/// of the output archive rather than aborting the whole batch; its error
+/// is not returned here. [`crop_batch`]'s sibling [`crop_batch_report`]
+/// takes the same inputs and returns the per-file outcomes with errors.
+///
+/// `on_progress`, if given, is called as `(files_done, files_total)` after
+/// each file is processed; `cancel`, if given, is checked at the same
+/// point, so a batch of hundreds of scanned files can be aborted between
+/// files instead of blocking the Web Worker until it finishes. A
+/// cancelled batch returns the ZIP of whatever files were cropped before
+/// the cancellation was observed.
+#[wasm_bindgen]
+pub fn crop_batch(
+    zip_bytes: &[u8],
+    spec: BatchCropSpec,
+    explicit_box: Vec<f32>,
+    auto_options: AutoCropOptions,
+    on_progress: Option<Function>,
+    cancel: Option<CancelToken>,
+) -> Result<Vec<u8>, JsValue> {
+    let explicit_box: [f32; 4] = explicit_box
+        .try_into()
+        .map_err(|_| JsValue::from_str("explicit_box must have exactly 4 components"))?;
+
+    // Only `AutoCropAcrossBatch` needs to see every file before cropping
+    // any of them; the other specs can read, crop, and write one entry at
+    // a time, so only one file's decompressed bytes are ever live at once.
+    let shared_box = matches!(spec, BatchCropSpec::AutoCropAcrossBatch)
+        .then(|| compute_shared_box(zip_bytes, auto_options))
+        .flatten();
+
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|e| JsValue::from_str(&format!("failed to read batch ZIP: {e}")))?;
+    let reporter = ProgressReporter::new(on_progress.as_ref(), cancel.as_ref(), archive.len() as u32);
+
+    let mut output = Vec::new();
+    {
+        let mut writer = ZipWriter::new(Cursor::new(&mut output));
+        let options = FileOptions::default();
+
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| JsValue::from_str(&format!("failed to read ZIP entry {i}: {e}")))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            let mut bytes = Vec::new();
+            entry
+                .read_to_end(&mut bytes)
+                .map_err(|e| JsValue::from_str(&format!("failed to read {name}: {e}")))?;
+            drop(entry);
+
+            match crop_one(&bytes, spec, explicit_box, auto_options, shared_box) {
+                Ok(cropped) => {
+                    writer
+                        .start_file(&name, options)
+                        .map_err(|e| JsValue::from_str(&format!("failed to write {name} to output ZIP: {e}")))?;
+                    writer
+                        .write_all(&cropped)
+                        .map_err(|e| JsValue::from_str(&format!("failed to write {name} to output ZIP: {e}")))?;
+                }
+                Err(_) => {
+                    // One corrupt file must not abort the batch; it's simply
+                    // left out of the output archive.
+                }
+            }
+
+            if reporter.report(i as u32 + 1) {
+                break;
+            }
+        }
+        writer
+            .finish()
+            .map_err(|e| JsValue::from_str(&format!("failed to finalize output ZIP: {e}")))?;
+    }
+
+    Ok(output)
+}
+
+/// Same inputs as [`crop_batch`], but returns a per-file success/failure
+/// report instead of the cropped ZIP, for callers that want to show the
+/// user which files failed and why. `on_progress` and `cancel` behave the
+/// same way as in `crop_batch`; a cancelled run returns the results
+/// gathered for whatever files were processed before cancellation.
+#[wasm_bindgen]
+pub fn crop_batch_report(
+    zip_bytes: &[u8],
+    spec: BatchCropSpec,
+    explicit_box: Vec<f32>,
+    auto_options: AutoCropOptions,
+    on_progress: Option<Function>,
+    cancel: Option<CancelToken>,
+) -> Result<Vec<BatchFileResult>, JsValue> {
+    let explicit_box: [f32; 4] = explicit_box
+        .try_into()
+        .map_err(|_| JsValue::from_str("explicit_box must have exactly 4 components"))?;
+
+    // Must match `crop_batch`'s shared_box handling exactly, or this report
+    // would show the user per-file outcomes that don't reflect what
+    // `crop_batch` actually produces for `AutoCropAcrossBatch`.
+    let shared_box = matches!(spec, BatchCropSpec::AutoCropAcrossBatch)
+        .then(|| compute_shared_box(zip_bytes, auto_options))
+        .flatten();
+
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|e| JsValue::from_str(&format!("failed to read batch ZIP: {e}")))?;
+    let reporter = ProgressReporter::new(on_progress.as_ref(), cancel.as_ref(), archive.len() as u32);
+
+    let mut results = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => {
+                results.push(BatchFileResult { name: format!("entry {i}"), ok: false, error: Some(e.to_string()), bytes: None });
+                continue;
+            }
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        if let Err(e) = entry.read_to_end(&mut bytes) {
+            results.push(BatchFileResult { name, ok: false, error: Some(e.to_string()), bytes: None });
+            continue;
+        }
+
+        match crop_one(&bytes, spec, explicit_box, auto_options, shared_box) {
+            Ok(cropped) => results.push(BatchFileResult { name, ok: true, error: None, bytes: Some(cropped) }),
+            Err(e) => results.push(BatchFileResult { name, ok: false, error: Some(e.as_string().unwrap_or_default()), bytes: None }),
+        }
+
+        if reporter.report(i as u32 + 1) {
+            break;
+        }
+    }
+
+    Ok(results)
+}
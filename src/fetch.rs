@@ -0,0 +1,70 @@
+//! Fetching source PDFs directly from a URL, so the hosted page can crop
+//! documents linked elsewhere without a manual download/upload round-trip.
+
+use futures_util::future::{select, Either};
+use gloo_net::http::Request;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen::prelude::*;
+
+/// Default request timeout when the caller passes `0`.
+const DEFAULT_TIMEOUT_MS: u32 = 30_000;
+
+/// Fetches the PDF at `url` and returns its raw bytes.
+///
+/// `timeout_ms` bounds how long the fetch may take; `0` falls back to
+/// [`DEFAULT_TIMEOUT_MS`] rather than waiting forever, since an unbounded
+/// fetch from a Web Worker has no way to be cancelled later. HTTP and CORS
+/// failures are surfaced as `JsValue` errors with a descriptive message
+/// rather than the opaque errors `fetch` itself produces.
+#[wasm_bindgen]
+pub async fn fetch_pdf_bytes(url: &str, timeout_ms: u32) -> Result<Vec<u8>, JsValue> {
+    let timeout_ms = if timeout_ms == 0 { DEFAULT_TIMEOUT_MS } else { timeout_ms };
+
+    let request = Request::get(url)
+        .build()
+        .map_err(|e| JsValue::from_str(&format!("invalid PDF url: {e}")))?;
+
+    let response = match select(Box::pin(request.send()), Box::pin(TimeoutFuture::new(timeout_ms))).await {
+        Either::Left((result, _)) => {
+            result.map_err(|e| JsValue::from_str(&format!("failed to fetch PDF: {e}")))?
+        }
+        Either::Right(_) => return Err(JsValue::from_str("timed out fetching PDF")),
+    };
+
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!(
+            "server returned {} {} for {url}",
+            response.status(),
+            response.status_text()
+        )));
+    }
+
+    // `gloo_net::Response::binary` buffers the whole body via the
+    // underlying `ArrayBuffer` rather than streaming it incrementally, so
+    // this still takes one extra copy for large PDFs. True streaming would
+    // mean reading `response.body()`'s `ReadableStream` chunk by chunk.
+    response
+        .binary()
+        .await
+        .map_err(|e| JsValue::from_str(&format!("failed to read PDF body: {e}")))
+}
+
+/// Fetches a PDF from `url` and crops it with an explicit crop box, in one
+/// step, for callers that don't need to inspect the bytes in between.
+#[wasm_bindgen]
+pub async fn crop_from_url(
+    url: &str,
+    timeout_ms: u32,
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+) -> Result<Vec<u8>, JsValue> {
+    let bytes = fetch_pdf_bytes(url, timeout_ms).await?;
+    pdfcrop::wasm::set_crop_boxes(
+        &bytes,
+        (0..pdfcrop::wasm::page_count(&bytes)?)
+            .map(|page_index| pdfcrop::wasm::CropBox { page_index, x0, y0, x1, y1 })
+            .collect(),
+    )
+}
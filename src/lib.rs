@@ -2,6 +2,28 @@
 //!
 //! This is a thin wrapper that re-exports the WASM bindings from the main pdfcrop crate.
 //! All the heavy lifting is done in the parent crate's src/wasm.rs module.
+//!
+//! A few bindings that are specific to the web app (rather than the core
+//! cropping logic) live directly in this crate, composed on top of the
+//! re-exported primitives below.
 
 // Re-export all WASM bindings from pdfcrop
 pub use pdfcrop::wasm::*;
+
+mod auto_crop;
+pub use auto_crop::*;
+
+mod fetch;
+pub use fetch::*;
+
+mod preview;
+pub use preview::*;
+
+mod raster_export;
+pub use raster_export::*;
+
+mod batch;
+pub use batch::*;
+
+mod progress;
+pub use progress::*;
@@ -0,0 +1,93 @@
+//! Rendering page previews for interactive, drag-to-select crop boxes.
+//!
+//! The web app rasterizes a page to a `<canvas>`, lets the user drag a
+//! rectangle over it in device pixels (top-left origin), and then needs
+//! that rectangle translated into the PDF crop-box coordinates (bottom-left
+//! origin, points) that [`pdfcrop::wasm::set_crop_boxes`] expects.
+
+use pdfcrop::wasm::{render_page_rgba, CropBox};
+use wasm_bindgen::prelude::*;
+
+/// A rasterized page: RGBA pixel data plus the dimensions needed to size
+/// the canvas it's drawn into.
+#[wasm_bindgen(getter_with_clone)]
+pub struct PagePreview {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Rasterizes `page_index` at `scale` (device pixels per PDF point, so
+/// `scale = dpi / 72.0`) and returns the RGBA pixel data together with its
+/// width/height, so the caller can size its canvas and draw the bitmap
+/// from a single render rather than rendering the page twice.
+#[wasm_bindgen]
+pub fn render_page_to_bitmap(pdf_bytes: &[u8], page_index: u32, scale: f32) -> Result<PagePreview, JsValue> {
+    let dpi = scale * 72.0;
+    let rendered = render_page_rgba(pdf_bytes, page_index, dpi)?;
+    Ok(PagePreview { width: rendered.width, height: rendered.height, pixels: rendered.pixels })
+}
+
+/// Validates and unpacks a `[x0, y0, x1, y1]` media box. Kept as a plain
+/// Rust helper, separate from the `#[wasm_bindgen]` entry point, so the
+/// error path can be exercised in unit tests without going through
+/// `JsValue`, which only works on a real wasm32+JS host.
+fn parse_media_box(media_box: Vec<f32>) -> Result<[f32; 4], &'static str> {
+    media_box.try_into().map_err(|_| "media_box must have exactly 4 components")
+}
+
+/// Converts a crop rectangle dragged on a rendered canvas (top-left origin,
+/// device pixels) into a [`CropBox`] (bottom-left origin, PDF points).
+///
+/// `scale` is the same device-pixels-per-point value passed to
+/// [`render_page_to_bitmap`]; `media_box` is the page's `[x0, y0, x1, y1]`
+/// as returned by `pdfcrop::wasm::page_media_box`.
+#[wasm_bindgen]
+pub fn canvas_rect_to_crop_box(
+    page_index: u32,
+    media_box: Vec<f32>,
+    scale: f32,
+    rect_x: f32,
+    rect_y: f32,
+    rect_width: f32,
+    rect_height: f32,
+) -> Result<CropBox, JsValue> {
+    let [mx0, my0, _mx1, my1] = parse_media_box(media_box).map_err(JsValue::from_str)?;
+
+    let page_height_px = (my1 - my0) * scale;
+
+    // Flip the y axis (canvas grows downward, PDF space grows upward) and
+    // rescale device pixels back to points.
+    let x0 = mx0 + rect_x / scale;
+    let x1 = mx0 + (rect_x + rect_width) / scale;
+    let y1 = my0 + (page_height_px - rect_y) / scale;
+    let y0 = my0 + (page_height_px - (rect_y + rect_height)) / scale;
+
+    Ok(CropBox { page_index, x0, y0, x1, y1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_rect_to_crop_box_flips_y_and_rescales() {
+        // A 792-point-tall page rendered at 2x (scale = device px / point).
+        let media_box = vec![0.0, 0.0, 612.0, 792.0];
+        let result = canvas_rect_to_crop_box(3, media_box, 2.0, 20.0, 40.0, 100.0, 60.0).unwrap();
+
+        assert_eq!(result.page_index, 3);
+        assert_eq!(result.x0, 10.0);
+        assert_eq!(result.x1, 60.0);
+        // y is flipped: a rect near the top of the canvas ends up near the
+        // top of the page in PDF points (high y).
+        assert_eq!(result.y1, 772.0);
+        assert_eq!(result.y0, 742.0);
+    }
+
+    #[test]
+    fn parse_media_box_rejects_wrong_length() {
+        let media_box = vec![0.0, 0.0, 612.0];
+        assert!(parse_media_box(media_box).is_err());
+    }
+}
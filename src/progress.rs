@@ -0,0 +1,82 @@
+//! Progress reporting and cooperative cancellation for long-running crop
+//! jobs, so a page doing heavy per-page rasterization in a Web Worker can
+//! show a progress bar and let the user abort between pages.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+/// A handle the caller can flip to request that an in-progress crop job
+/// stop at the next page boundary. Cloning shares the same underlying
+/// flag, so the JS side can hold one handle while the running job holds
+/// another.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[wasm_bindgen]
+impl CancelToken {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CancelToken {
+        CancelToken { cancelled: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Requests cancellation. Safe to call from any thread/task; takes
+    /// effect the next time the running job checks [`CancelToken::is_cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        CancelToken::new()
+    }
+}
+
+/// Reports `(pages_done, pages_total)` to an optional JS callback, and
+/// checks an optional [`CancelToken`] between pages. Shared by every crop
+/// entry point that processes pages one at a time.
+pub(crate) struct ProgressReporter<'a> {
+    on_progress: Option<&'a Function>,
+    cancel: Option<&'a CancelToken>,
+    pages_total: u32,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub(crate) fn new(on_progress: Option<&'a Function>, cancel: Option<&'a CancelToken>, pages_total: u32) -> Self {
+        ProgressReporter { on_progress, cancel, pages_total }
+    }
+
+    /// Call after finishing `pages_done` pages (1-based count of pages
+    /// completed so far). Returns `true` if the job should stop now
+    /// because cancellation was requested.
+    pub(crate) fn report(&self, pages_done: u32) -> bool {
+        if let Some(callback) = self.on_progress {
+            let _ = callback.call2(
+                &JsValue::NULL,
+                &JsValue::from_f64(pages_done as f64),
+                &JsValue::from_f64(self.pages_total as f64),
+            );
+        }
+        self.cancel.map(|c| c.is_cancelled()).unwrap_or(false)
+    }
+}
+
+/// The outcome of a cancellable job: either it ran to completion, or it
+/// was stopped partway through and returns whatever was produced for the
+/// pages completed so far.
+#[wasm_bindgen(getter_with_clone)]
+pub struct PartialResult {
+    pub bytes: Vec<u8>,
+    pub cancelled: bool,
+    pub pages_completed: u32,
+}
@@ -0,0 +1,109 @@
+//! Exporting cropped pages as compressed raster images, for users who want
+//! figure extraction or thumbnails rather than a cropped PDF.
+
+use image::{codecs::jpeg::JpegEncoder, ImageBuffer, ImageEncoder, Rgba};
+use pdfcrop::wasm::{page_count, render_page_rgba};
+use wasm_bindgen::prelude::*;
+
+/// Raster output format for [`export_pages_as_images`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RasterFormat {
+    Png,
+    Jpeg,
+}
+
+/// How the rendered pages are packaged.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RasterLayout {
+    /// One encoded image per page.
+    PerPage,
+    /// All pages stitched into a single image, stacked vertically.
+    ContactSheet,
+}
+
+/// One page's worth of encoded image bytes, keyed by its index in the
+/// source PDF (not the index within the selected range).
+#[wasm_bindgen(getter_with_clone)]
+pub struct PageImage {
+    pub page_index: u32,
+    pub bytes: Vec<u8>,
+}
+
+fn encode(image: &ImageBuffer<Rgba<u8>, Vec<u8>>, format: RasterFormat, jpeg_quality: u8) -> Result<Vec<u8>, JsValue> {
+    let mut out = Vec::new();
+    match format {
+        RasterFormat::Png => image
+            .write_with_encoder(image::codecs::png::PngEncoder::new(&mut out))
+            .map_err(|e| JsValue::from_str(&format!("failed to encode PNG: {e}")))?,
+        RasterFormat::Jpeg => {
+            // JPEG has no alpha channel; flatten onto white first so
+            // transparent margins from the crop don't turn black.
+            let rgb = image::RgbImage::from_fn(image.width(), image.height(), |x, y| {
+                let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+                let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+                image::Rgb([blend(r), blend(g), blend(b)])
+            });
+            JpegEncoder::new_with_quality(&mut out, jpeg_quality)
+                .write_image(&rgb, rgb.width(), rgb.height(), image::ColorType::Rgb8.into())
+                .map_err(|e| JsValue::from_str(&format!("failed to encode JPEG: {e}")))?;
+        }
+    }
+    Ok(out)
+}
+
+/// Renders `pdf_bytes` pages `first_page..=last_page` (inclusive, 0-based)
+/// at `dpi` and encodes each as `format`. `jpeg_quality` (1-100) is ignored
+/// for PNG. With [`RasterLayout::ContactSheet`] the pages are stacked into
+/// one tall image instead of being returned individually.
+#[wasm_bindgen]
+pub fn export_pages_as_images(
+    pdf_bytes: &[u8],
+    first_page: u32,
+    last_page: u32,
+    dpi: f32,
+    format: RasterFormat,
+    jpeg_quality: u8,
+    layout: RasterLayout,
+) -> Result<Vec<PageImage>, JsValue> {
+    let total_pages = page_count(pdf_bytes)?;
+    if last_page >= total_pages || first_page > last_page {
+        return Err(JsValue::from_str(&format!(
+            "page range {first_page}..={last_page} is out of bounds for a {total_pages}-page document"
+        )));
+    }
+
+    let mut pages = Vec::with_capacity((last_page - first_page + 1) as usize);
+    for page_index in first_page..=last_page {
+        let rendered = render_page_rgba(pdf_bytes, page_index, dpi)?;
+        let image = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(rendered.width, rendered.height, rendered.pixels)
+            .ok_or_else(|| JsValue::from_str("rendered page had an inconsistent buffer size"))?;
+        pages.push((page_index, image));
+    }
+
+    match layout {
+        RasterLayout::PerPage => pages
+            .into_iter()
+            .map(|(page_index, image)| {
+                Ok(PageImage { page_index, bytes: encode(&image, format, jpeg_quality)? })
+            })
+            .collect(),
+        RasterLayout::ContactSheet => {
+            let width = pages.iter().map(|(_, img)| img.width()).max().unwrap_or(0);
+            let total_height: u32 = pages.iter().map(|(_, img)| img.height()).sum();
+
+            let mut sheet = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_pixel(width, total_height, Rgba([255, 255, 255, 255]));
+            let mut y_offset = 0;
+            for (_, image) in &pages {
+                image::imageops::overlay(&mut sheet, image, 0, y_offset as i64);
+                y_offset += image.height();
+            }
+
+            Ok(vec![PageImage {
+                page_index: first_page,
+                bytes: encode(&sheet, format, jpeg_quality)?,
+            }])
+        }
+    }
+}